@@ -6,12 +6,19 @@
 // except according to those terms.
 
 //! Provides an interface to the SPI bus and the automatic SPI transfer engine.
-//!
-//! Currently does not implement an accumulator.
 
-use std::{io, time};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, Ordering},
+        Arc, Mutex,
+    },
+    thread, time,
+};
 use wpilib_sys::*;
 
+use crate::dio::{DigitalInput, DigitalOutput};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[repr(i32)]
 pub enum Port {
@@ -150,6 +157,155 @@ impl Spi {
     }
 }
 
+/// A [`Spi`] bus handle shared between several [`SpiDevice`]s.
+pub type SharedSpi = Arc<Mutex<Spi>>;
+
+impl Spi {
+    /// Wraps this bus in an `Arc<Mutex<_>>` so it can be shared between
+    /// several [`SpiDevice`]s.
+    pub fn into_shared(self) -> SharedSpi {
+        Arc::new(Mutex::new(self))
+    }
+}
+
+/// A single step in an [`SpiDevice::transaction`].
+#[derive(Debug)]
+pub enum Operation<'a> {
+    /// Read into the given buffer.
+    Read(&'a mut [u8]),
+    /// Write the given buffer.
+    Write(&'a [u8]),
+    /// Write `write` while simultaneously reading back into `read`.
+    Transfer(&'a mut [u8], &'a [u8]),
+    /// Write and read back into the same buffer.
+    TransferInPlace(&'a mut [u8]),
+    /// Delay for the given number of nanoseconds without ending the transaction.
+    DelayNs(u32),
+}
+
+/// Runs a sequence of [`Operation`]s against a locked `Spi` bus.
+fn run_operations(spi: &mut Spi, operations: &mut [Operation<'_>]) -> io::Result<()> {
+    for op in operations {
+        match op {
+            Operation::Read(buf) => {
+                spi.read(false, buf)?;
+            }
+            Operation::Write(data) => {
+                spi.write(data)?;
+            }
+            Operation::Transfer(read, write) => {
+                let len = read.len().max(write.len());
+                let mut to_send = vec![0u8; len];
+                to_send[..write.len()].copy_from_slice(write);
+                let mut received = vec![0u8; len];
+                unsafe { spi.transaction_into(&to_send, received.as_mut_ptr()) }?;
+                let n = read.len().min(len);
+                read[..n].copy_from_slice(&received[..n]);
+            }
+            Operation::TransferInPlace(words) => {
+                let ptr = words.as_mut_ptr();
+                unsafe { spi.transaction_into(words, ptr) }?;
+            }
+            Operation::DelayNs(ns) => {
+                thread::sleep(time::Duration::from_nanos(u64::from(*ns)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A single device on a shared SPI bus, with its own chip select.
+///
+/// embedded-hal 1.0 splits the shared `SpiBus` from per-device `SpiDevice`:
+/// a device owns its chip select and is responsible for transaction
+/// sequencing. A `Spi`'s hardware chip select is fixed to the `Port` it was
+/// created with, so it can't be reassigned per device; instead `SpiDevice`
+/// drives an ordinary [`DigitalOutput`] as its chip select (the same
+/// GPIO-driven approach [`SoftSpi`] uses), asserting it around each
+/// transaction and taking the shared bus's lock for the duration so several
+/// `SpiDevice`s can fan out one `Spi` without their transactions
+/// interleaving or fighting over each other's chip select.
+#[derive(Debug)]
+pub struct SpiDevice {
+    bus: SharedSpi,
+    cs: DigitalOutput,
+    cs_active_high: bool,
+}
+
+impl SpiDevice {
+    /// Wraps a shared SPI bus handle as a single device with its own chip
+    /// select line, which idles deasserted.
+    pub fn new(bus: SharedSpi, mut cs: DigitalOutput, cs_active_high: bool) -> HalResult<Self> {
+        cs.set(!cs_active_high)?;
+        Ok(SpiDevice {
+            bus,
+            cs,
+            cs_active_high,
+        })
+    }
+
+    /// Runs a sequence of operations as a single transaction, asserting this
+    /// device's chip select and holding the bus lock for the duration so no
+    /// other device's transaction can interleave with this one.
+    pub fn transaction(&mut self, operations: &mut [Operation<'_>]) -> io::Result<()> {
+        let mut spi = self.bus.lock().unwrap();
+        hal_to_io(self.cs.set(self.cs_active_high))?;
+        let result = run_operations(&mut spi, operations);
+        let deasserted = hal_to_io(self.cs.set(!self.cs_active_high));
+        result.and(deasserted)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::ErrorType for SpiDevice {
+    type Error = SpiError;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::SpiDevice for SpiDevice {
+    fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        use embedded_hal::spi::Operation as EhOp;
+
+        let mut spi = self.bus.lock().unwrap();
+        hal_to_io(self.cs.set(self.cs_active_high)).map_err(SpiError)?;
+
+        let mut result = Ok(());
+        for op in operations {
+            result = match op {
+                EhOp::Read(buf) => spi.read(false, buf).map(|_| ()),
+                EhOp::Write(data) => spi.write(data).map(|_| ()),
+                EhOp::Transfer(read, write) => {
+                    let len = read.len().max(write.len());
+                    let mut to_send = vec![0u8; len];
+                    to_send[..write.len()].copy_from_slice(write);
+                    let mut received = vec![0u8; len];
+                    unsafe { spi.transaction_into(&to_send, received.as_mut_ptr()) }.map(|_| {
+                        let n = read.len().min(len);
+                        read[..n].copy_from_slice(&received[..n]);
+                    })
+                }
+                EhOp::TransferInPlace(words) => {
+                    let ptr = words.as_mut_ptr();
+                    unsafe { spi.transaction_into(words, ptr) }.map(|_| ())
+                }
+                EhOp::DelayNs(ns) => {
+                    thread::sleep(time::Duration::from_nanos(u64::from(*ns)));
+                    Ok(())
+                }
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let deasserted = hal_to_io(self.cs.set(!self.cs_active_high)).map_err(SpiError);
+        result.map_err(SpiError).and(deasserted)
+    }
+}
+
 impl io::Read for Spi {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         self.read(false, buf)
@@ -167,9 +323,101 @@ impl embedded_hal::blocking::spi::Transfer<u8> for Spi {
     }
 }
 
+/// Error type for the embedded-hal 1.0 [`embedded_hal::spi::SpiBus`] impl.
+///
+/// Wraps the `io::Error` produced by the underlying HAL call. `embedded-hal`'s
+/// `spi::Error` can't be implemented directly on `io::Error`, since neither the
+/// trait nor the type is local to this crate.
+#[cfg(feature = "embedded-hal-1")]
+#[derive(Debug)]
+pub struct SpiError(io::Error);
+
+#[cfg(feature = "embedded-hal-1")]
+impl From<io::Error> for SpiError {
+    fn from(err: io::Error) -> Self {
+        SpiError(err)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl std::fmt::Display for SpiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl std::error::Error for SpiError {}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::Error for SpiError {
+    fn kind(&self) -> embedded_hal::spi::ErrorKind {
+        embedded_hal::spi::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::ErrorType for Spi {
+    type Error = SpiError;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::SpiBus<u8> for Spi {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        Spi::read(self, false, words)?;
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        Spi::write(self, words)?;
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        // `HAL_TransactionSPI` requires equal-length send/receive buffers;
+        // zero-pad the shorter side and transact over the longer length.
+        let len = read.len().max(write.len());
+        let mut to_send = vec![0u8; len];
+        to_send[..write.len()].copy_from_slice(write);
+        let mut received = vec![0u8; len];
+        unsafe { self.transaction_into(&to_send, received.as_mut_ptr()) }?;
+        let n = read.len().min(len);
+        read[..n].copy_from_slice(&received[..n]);
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        let ptr = words.as_mut_ptr();
+        unsafe { self.transaction_into(words, ptr) }?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A single decoded frame of automatic SPI data, as produced by
+/// [`AutoSpi::read_frames`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpiFrame {
+    /// The FPGA timestamp at which this frame was captured.
+    pub timestamp: time::Duration,
+    /// The received data bytes, with each word's LSB packed back together.
+    pub data: Vec<u8>,
+}
+
 /// Automatic SPI transfer engine.
 #[derive(Debug)]
-pub struct AutoSpi(Spi);
+pub struct AutoSpi {
+    spi: Spi,
+    /// The `1 + data_len + zero_size` word stride of a received frame, once
+    /// known from [`set_transmit_data`](Self::set_transmit_data).
+    frame_words: Option<usize>,
+    /// Words read by [`read_frames`](Self::read_frames) that didn't complete
+    /// a whole frame, held over for the next call.
+    leftover: Vec<u32>,
+}
 
 impl AutoSpi {
     /// Initialize automatic SPI transfer engine.
@@ -178,35 +426,42 @@ impl AutoSpi {
     /// This will error if an engine is currently already allocated.
     pub fn new(spi: Spi, buffer_size: i32) -> HalResult<Self> {
         hal_call!(HAL_InitSPIAuto(spi.port, buffer_size))?;
-        Ok(Self(spi))
+        Ok(AutoSpi {
+            spi,
+            frame_words: None,
+            leftover: Vec::new(),
+        })
     }
 
     /// Frees the automatic SPI transfer engine, releasing the underlying `Spi`.
     pub fn stop(self) -> Spi {
         // Spi::drop (HAL_CloseSPI) will ensure the auto SPI is freed if we get dropped.
-        let _ = hal_call!(HAL_FreeSPIAuto(self.0.port));
-        self.0
+        let _ = hal_call!(HAL_FreeSPIAuto(self.spi.port));
+        self.spi
     }
 
     pub fn set_transmit_data(&mut self, to_send: &[u8], zero_size: i32) -> HalResult<()> {
         hal_call!(HAL_SetSPIAutoTransmitData(
-            self.0.port,
+            self.spi.port,
             to_send.as_ptr(),
             to_send.len() as i32,
             zero_size
-        ))
+        ))?;
+        self.frame_words = Some(1 + to_send.len() + zero_size as usize);
+        self.leftover.clear();
+        Ok(())
     }
 
     pub fn start_rate(&mut self, period: time::Duration) -> HalResult<()> {
-        hal_call!(HAL_StartSPIAutoRate(self.0.port, period.as_secs_f64()))
+        hal_call!(HAL_StartSPIAutoRate(self.spi.port, period.as_secs_f64()))
     }
 
     pub fn pause(&mut self) -> HalResult<()> {
-        hal_call!(HAL_StopSPIAuto(self.0.port))
+        hal_call!(HAL_StopSPIAuto(self.spi.port))
     }
 
     pub fn force_read(&mut self) -> HalResult<()> {
-        hal_call!(HAL_ForceSPIAutoRead(self.0.port))
+        hal_call!(HAL_ForceSPIAutoRead(self.spi.port))
     }
 
     /**
@@ -229,17 +484,440 @@ impl AutoSpi {
         timeout: time::Duration,
     ) -> HalResult<i32> {
         hal_call!(HAL_ReadSPIAutoReceivedData(
-            self.0.port,
+            self.spi.port,
             buffer.as_mut_ptr(),
             buffer.len() as _,
             timeout.as_secs_f64()
         ))
     }
 
+    /// Reads up to `max_frames` whole frames of automatic SPI data.
+    ///
+    /// Built on [`read_received_data`](Self::read_received_data), this knows
+    /// the `1 + data_len + zero_size` word frame stride (one timestamp word,
+    /// then one word per data byte) set by
+    /// [`set_transmit_data`](Self::set_transmit_data), so callers don't have
+    /// to hand-reassemble the `[timestamp_word, data_word, ...]` layout or
+    /// cope with partial transfers themselves: only whole frames are
+    /// returned, and any leftover words are buffered for the next call.
+    ///
+    /// Panics if called before `set_transmit_data`.
+    pub fn read_frames(
+        &mut self,
+        max_frames: usize,
+        timeout: time::Duration,
+    ) -> HalResult<Vec<SpiFrame>> {
+        let frame_words = self
+            .frame_words
+            .expect("set_transmit_data must be called before read_frames");
+
+        let carried = self.leftover.len();
+        let mut buf = vec![0u32; carried + max_frames * frame_words];
+        buf[..carried].copy_from_slice(&self.leftover);
+
+        // When called with an empty buffer, `read_received_data` returns the
+        // number of words *available* rather than copied, per its own doc
+        // comment -- clamp to what was actually written into `buf`.
+        let read = (self.read_received_data(&mut buf[carried..], timeout)? as usize)
+            .min(buf.len() - carried);
+        let available = carried + read;
+        let whole_frames = available / frame_words;
+
+        let frames = buf[..whole_frames * frame_words]
+            .chunks_exact(frame_words)
+            .map(|frame| SpiFrame {
+                timestamp: time::Duration::from_micros(u64::from(frame[0])),
+                data: frame[1..].iter().map(|&word| word as u8).collect(),
+            })
+            .collect();
+
+        self.leftover = buf[whole_frames * frame_words..available].to_vec();
+        Ok(frames)
+    }
+
     pub fn dropped_count(&mut self) -> i32 {
         // All this should guarantee we are the auto SPI.
         // If not, something has gone horribly wrong.
-        hal_call!(HAL_GetSPIAutoDroppedCount(self.0.port)).unwrap()
+        hal_call!(HAL_GetSPIAutoDroppedCount(self.spi.port)).unwrap()
+    }
+}
+
+/// Sample accumulated by an `SpiAccumulator`'s background reader thread.
+#[derive(Debug, Default, Copy, Clone)]
+struct AccumulatorSample {
+    sum: i64,
+    count: u32,
+    last_value: i32,
+    integrator: f64,
+}
+
+/// Accumulates samples streamed off the automatic SPI transfer engine.
+///
+/// This turns the low-level [`AutoSpi`] frame buffer into something a real
+/// sensor driver can build on: a background thread drains frames via
+/// [`AutoSpi::read_received_data`], extracts the payload bits described by
+/// `data_shift`/`data_size`, and maintains a running sum, sample count, last
+/// value, and integrator -- the same shape as the accumulator on an analog
+/// input, but fed by a continuous-read SPI ADC such as the AD7172.
+#[derive(Debug)]
+pub struct SpiAccumulator {
+    #[allow(dead_code)]
+    auto: Arc<Mutex<AutoSpi>>,
+    sample: Arc<Mutex<AccumulatorSample>>,
+    center: Arc<AtomicI32>,
+    deadband: Arc<AtomicI32>,
+    running: Arc<AtomicBool>,
+    reader: Option<thread::JoinHandle<()>>,
+}
+
+impl SpiAccumulator {
+    /// Starts an accumulator on top of `spi`'s automatic SPI transfer engine.
+    ///
+    /// `command` is the periodic command sent every `period`; `xfer_size` is
+    /// the number of zero-padded bytes clocked back in response, as passed to
+    /// [`AutoSpi::set_transmit_data`]. A received frame is only counted if
+    /// `frame & valid_mask == valid_value`, where `frame` is the `command.len()
+    /// + xfer_size` data bytes reassembled (per `big_endian`) into an integer.
+    /// The `data_size`-bit payload starting at bit `data_shift` is then
+    /// extracted, sign-extended when `is_signed`, and has `center` subtracted
+    /// before being accumulated; samples whose centered magnitude is below
+    /// `deadband` are ignored.
+    ///
+    /// `command.len() + xfer_size` (the data bytes reassembled into `frame`)
+    /// must be at most 4, since `valid_mask`/`valid_value`/`data_shift` all
+    /// address bits of a single 32-bit reassembled word; panics otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spi: Spi,
+        period: time::Duration,
+        command: &[u8],
+        xfer_size: i32,
+        valid_mask: u32,
+        valid_value: u32,
+        data_shift: u32,
+        data_size: u32,
+        is_signed: bool,
+        big_endian: bool,
+    ) -> HalResult<Self> {
+        let frame_len = command.len() + xfer_size as usize;
+        assert!(
+            frame_len <= 4,
+            "SpiAccumulator frames are reassembled into a u32, so command.len() + xfer_size \
+             must be at most 4 bytes (got {})",
+            frame_len
+        );
+
+        let mut auto = AutoSpi::new(spi, 4096)?;
+        auto.set_transmit_data(command, xfer_size)?;
+        auto.start_rate(period)?;
+
+        // One timestamp word, then one word per received data byte.
+        let frame_words = 1 + frame_len;
+
+        let auto = Arc::new(Mutex::new(auto));
+        let sample = Arc::new(Mutex::new(AccumulatorSample::default()));
+        let center = Arc::new(AtomicI32::new(0));
+        let deadband = Arc::new(AtomicI32::new(0));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let reader = {
+            let auto = Arc::clone(&auto);
+            let sample = Arc::clone(&sample);
+            let center = Arc::clone(&center);
+            let deadband = Arc::clone(&deadband);
+            let running = Arc::clone(&running);
+            thread::spawn(move || {
+                // A handful of frames' worth of headroom between polls.
+                let mut buf = vec![0u32; frame_words * 4];
+                while running.load(Ordering::Acquire) {
+                    let read = {
+                        let mut auto = auto.lock().unwrap();
+                        auto.read_received_data(&mut buf, time::Duration::from_millis(10))
+                    };
+                    let read = match read {
+                        Ok(read) => read as usize,
+                        Err(_) => continue,
+                    };
+                    let whole_frames = read / frame_words;
+                    if whole_frames == 0 {
+                        continue;
+                    }
+
+                    let center = center.load(Ordering::Relaxed);
+                    let deadband = deadband.load(Ordering::Relaxed);
+                    let mut sample = sample.lock().unwrap();
+                    for frame in buf[..whole_frames * frame_words].chunks_exact(frame_words) {
+                        let data = &frame[1..];
+                        let raw: u32 = if big_endian {
+                            data.iter()
+                                .fold(0u32, |acc, word| (acc << 8) | (word & 0xff))
+                        } else {
+                            data.iter()
+                                .rev()
+                                .fold(0u32, |acc, word| (acc << 8) | (word & 0xff))
+                        };
+                        if raw & valid_mask != valid_value {
+                            continue;
+                        }
+
+                        let mask = if data_size >= 32 {
+                            u32::MAX
+                        } else {
+                            (1u32 << data_size) - 1
+                        };
+                        let mut value = ((raw >> data_shift) & mask) as i32;
+                        if is_signed
+                            && data_size > 0
+                            && data_size < 32
+                            && value & (1i32 << (data_size - 1)) != 0
+                        {
+                            value = value.wrapping_sub(1i32 << data_size);
+                        }
+                        value = value.wrapping_sub(center);
+                        if value.unsigned_abs() < deadband.unsigned_abs() {
+                            continue;
+                        }
+
+                        sample.sum += i64::from(value);
+                        sample.count += 1;
+                        sample.last_value = value;
+                        sample.integrator += f64::from(value);
+                    }
+                }
+            })
+        };
+
+        Ok(SpiAccumulator {
+            auto,
+            sample,
+            center,
+            deadband,
+            running,
+            reader: Some(reader),
+        })
+    }
+
+    /// The most recently accumulated (centered) sample value.
+    pub fn value(&self) -> i32 {
+        self.sample.lock().unwrap().last_value
+    }
+
+    /// The number of samples accumulated since the last [`reset`](Self::reset).
+    pub fn count(&self) -> u32 {
+        self.sample.lock().unwrap().count
+    }
+
+    /// The average of all samples accumulated since the last [`reset`](Self::reset).
+    pub fn average(&self) -> f64 {
+        let sample = self.sample.lock().unwrap();
+        if sample.count == 0 {
+            0.0
+        } else {
+            sample.sum as f64 / f64::from(sample.count)
+        }
+    }
+
+    /// The running integral of accumulated samples.
+    pub fn integrator_value(&self) -> f64 {
+        self.sample.lock().unwrap().integrator
+    }
+
+    /// Clears the accumulated sum, count, and integrator.
+    pub fn reset(&mut self) {
+        *self.sample.lock().unwrap() = AccumulatorSample::default();
+    }
+
+    /// Sets the value subtracted from every sample before it's accumulated.
+    pub fn set_center(&mut self, center: i32) {
+        self.center.store(center, Ordering::Relaxed);
+    }
+
+    /// Sets the minimum centered magnitude a sample must have to be accumulated.
+    pub fn set_deadband(&mut self, deadband: i32) {
+        self.deadband.store(deadband, Ordering::Relaxed);
+    }
+}
+
+impl Drop for SpiAccumulator {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(reader) = self.reader.take() {
+            // Propagate a panic in the reader thread instead of swallowing
+            // it -- otherwise the accumulator would silently stop updating
+            // with no indication anything went wrong.
+            if let Err(panic) = reader.join() {
+                if !thread::panicking() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+        }
+    }
+}
+
+/// A software (bit-banged) SPI backend driven over ordinary digital IO.
+///
+/// Inspired by the `SyncSoftSpi` implementation in the thermostat firmware,
+/// `SoftSpi` toggles clock/MOSI/CS and samples MISO on plain
+/// [`DigitalOutput`]/[`DigitalInput`] channels instead of using one of the
+/// RoboRIO's five hardware SPI ports. Useful once those are exhausted, or
+/// when a device needs timing the hardware peripheral can't produce.
+#[derive(Debug)]
+pub struct SoftSpi {
+    clk: DigitalOutput,
+    mosi: DigitalOutput,
+    miso: DigitalInput,
+    cs: DigitalOutput,
+    opts: SpiOptions,
+    half_period: time::Duration,
+}
+
+impl SoftSpi {
+    /// Creates a bit-banged SPI bus over the given DIO channels.
+    ///
+    /// `half_period` is the delay held on each half of the clock cycle, and
+    /// so controls the bit rate. The clock idles according to
+    /// `opts.clk_idle_high` and the chip select idles deasserted.
+    pub fn new(
+        clk: DigitalOutput,
+        mosi: DigitalOutput,
+        miso: DigitalInput,
+        cs: DigitalOutput,
+        opts: SpiOptions,
+        half_period: time::Duration,
+    ) -> HalResult<Self> {
+        let mut soft = SoftSpi {
+            clk,
+            mosi,
+            miso,
+            cs,
+            opts,
+            half_period,
+        };
+        soft.clk.set(soft.opts.clk_idle_high)?;
+        soft.cs.set(false)?;
+        Ok(soft)
+    }
+
+    /// Changes the bit order, clock polarity, and sample edge used by
+    /// subsequent transactions.
+    pub fn set_opts(&mut self, opts: SpiOptions) {
+        self.opts = opts;
+    }
+
+    fn delay(&self) {
+        thread::sleep(self.half_period);
+    }
+
+    /// Clocks one bit out on MOSI and returns the bit sampled on MISO.
+    fn clock_bit(&mut self, out_bit: bool) -> HalResult<bool> {
+        let SpiOptions {
+            sample_on_trailing,
+            clk_idle_high,
+            ..
+        } = self.opts;
+
+        self.mosi.set(out_bit)?;
+        self.delay();
+
+        self.clk.set(!clk_idle_high)?;
+        self.delay();
+        let leading_sample = self.miso.get()?;
+
+        self.clk.set(clk_idle_high)?;
+        self.delay();
+        let trailing_sample = if sample_on_trailing {
+            Some(self.miso.get()?)
+        } else {
+            None
+        };
+
+        Ok(trailing_sample.unwrap_or(leading_sample))
+    }
+
+    fn transfer_byte(&mut self, out: u8) -> HalResult<u8> {
+        let mut received = 0u8;
+        for i in 0..8 {
+            let shift = if self.opts.msb_first { 7 - i } else { i };
+            let out_bit = (out >> shift) & 1 != 0;
+            if self.clock_bit(out_bit)? {
+                received |= 1 << shift;
+            }
+        }
+        Ok(received)
+    }
+
+    /// Clocks `to_send` out and the response in, without touching chip
+    /// select. This is the bare bus transfer used by the `SpiBus` impl below
+    /// and by [`transaction`](Self::transaction).
+    fn transfer_bytes(&mut self, to_send: &[u8]) -> HalResult<Vec<u8>> {
+        let mut received = Vec::with_capacity(to_send.len());
+        for &byte in to_send {
+            received.push(self.transfer_byte(byte)?);
+        }
+        Ok(received)
+    }
+
+    /// Performs an SPI send/receive transaction, asserting chip select for
+    /// its duration.
+    ///
+    /// This is a convenience for the common case of one device with no other
+    /// bus sharing; an `SpiDevice`-style wrapper that manages its own chip
+    /// select around several bare bus operations should use the bus
+    /// directly instead, the same way [`SpiDevice`] does for [`Spi`].
+    pub fn transaction(&mut self, to_send: &[u8]) -> HalResult<Vec<u8>> {
+        self.cs.set(true)?;
+        self.delay();
+
+        let received = self.transfer_bytes(to_send);
+
+        self.delay();
+        self.cs.set(false)?;
+        received
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::ErrorType for SoftSpi {
+    type Error = SpiError;
+}
+
+// `SpiBus` must never touch chip select -- per the embedded-hal 1.0 contract
+// that's exclusively an `SpiDevice` wrapper's job, asserted once around a
+// whole multi-operation transaction (e.g. `embedded-hal-bus`'s
+// `ExclusiveDevice`). These impls go through the CS-less `transfer_bytes`,
+// not the CS-managing `transaction` method, mirroring how `Spi`'s own
+// `SpiBus` impl (above) never calls `Spi::set_chip_select_active_*`.
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_hal::spi::SpiBus<u8> for SoftSpi {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        let received = hal_to_io(self.transfer_bytes(&vec![0; words.len()])).map_err(SpiError)?;
+        words.copy_from_slice(&received);
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        hal_to_io(self.transfer_bytes(words)).map_err(SpiError)?;
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        let len = read.len().max(write.len());
+        let mut to_send = vec![0u8; len];
+        to_send[..write.len()].copy_from_slice(write);
+        let received = hal_to_io(self.transfer_bytes(&to_send)).map_err(SpiError)?;
+        let n = read.len().min(len);
+        read[..n].copy_from_slice(&received[..n]);
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        let received = hal_to_io(self.transfer_bytes(words)).map_err(SpiError)?;
+        words.copy_from_slice(&received);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
     }
 }
 
@@ -257,3 +935,10 @@ fn io_result(rv: i32) -> io::Result<usize> {
         Ok(rv as usize)
     }
 }
+
+/// Maps a `HalResult` onto `io::Result`, for APIs (like `SpiDevice` and
+/// `SoftSpi`) that mix HAL digital IO calls with SPI calls that already
+/// return `io::Result`.
+fn hal_to_io<T>(result: HalResult<T>) -> io::Result<T> {
+    result.map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))
+}